@@ -0,0 +1,811 @@
+use std::collections::VecDeque;
+
+use bevy::core::FixedTimestep;
+use bevy::prelude::*;
+
+use rand::prelude::random;
+
+/// Whether the snake dies at the edge of the arena or wraps around to the
+/// opposite side.
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum WallMode {
+    Solid,
+    Wrap,
+}
+
+/// Runtime-configurable knobs for the arena and the snake's pacing, read
+/// by `snake_movement`, `size_scaling`/`position_translation` and
+/// `food_spawner` instead of hardcoded constants.
+#[derive(Debug, Copy, Clone)]
+pub struct GameConfig {
+    pub arena_width: u32,
+    pub arena_height: u32,
+    pub move_step: f64,
+    pub food_spawn_step: f64,
+    pub wall_mode: WallMode,
+}
+
+impl Default for GameConfig {
+    fn default() -> Self {
+        Self {
+            arena_width: 10,
+            arena_height: 10,
+            move_step: 1.5,
+            food_spawn_step: 1.0,
+            wall_mode: WallMode::Solid,
+        }
+    }
+}
+
+#[derive(Default, Debug, Eq, PartialEq, Copy, Clone)]
+struct Position {
+    x: i32,
+    y: i32,
+}
+
+struct Size {
+    width: f32,
+    height: f32,
+}
+
+impl Size {
+    pub fn square(x: f32) -> Self {
+        Self {
+            width: x,
+            height: x,
+        }
+    }
+}
+
+/// Bundles the snake game's resources, events and systems so it can be
+/// dropped into any `App` alongside `DefaultPlugins`. Construct with
+/// `SnakePlugin::default()` for the classic 10x10 solid-wall board, or
+/// supply a `GameConfig` to resize the arena or turn on wall-wrapping.
+#[derive(Default)]
+pub struct SnakePlugin {
+    pub config: GameConfig,
+}
+
+impl Plugin for SnakePlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.insert_resource(self.config)
+            .init_resource::<SnakeEntities>()
+            .init_resource::<LastTailPosition>()
+            .init_resource::<PendingIntentions>()
+            .init_resource::<Score>()
+            .init_resource::<AwaitingRestart>()
+            .add_event::<GrowthEvent>()
+            .add_event::<GameOverEvent>()
+            .add_startup_system(setup.system())
+            .add_startup_system(ui_setup.system())
+            .add_startup_system_to_stage(StartupStage::PostStartup, snake_setup.system())
+            .add_system(snake_input.system())
+            .add_system(scoreboard.system())
+            .add_system(restart_game.system().before("game_over"))
+            .add_system_set(
+                SystemSet::new()
+                    .with_run_criteria(FixedTimestep::step(self.config.move_step))
+                    .with_system(snake_movement.system().label("snake_movement"))
+                    .with_system(
+                        snake_eating
+                            .system()
+                            .label("eating")
+                            .after("snake_movement"),
+                    )
+                    .with_system(snake_growth.system().after("eating")),
+            )
+            .add_system_set(
+                SystemSet::new()
+                    .with_run_criteria(FixedTimestep::step(self.config.food_spawn_step))
+                    .with_system(food_spawner.system()),
+            )
+            .add_system(game_over.system().label("game_over"))
+            .add_system_to_stage(CoreStage::PostUpdate, size_scaling.system())
+            .add_system_to_stage(CoreStage::PostUpdate, position_translation.system());
+    }
+}
+
+fn setup(mut commands: Commands, mut materials: ResMut<Assets<ColorMaterial>>) {
+    commands.spawn_bundle(OrthographicCameraBundle::new_2d());
+
+    commands.insert_resource(SnakeMaterials {
+        head_material: materials.add(Color::rgb(0.7, 0.7, 0.7).into()),
+        segment_material: materials.add(Color::rgb(0.3, 0.3, 0.3).into()),
+        food_material: materials.add(Color::rgb(1.0, 1.0, 1.0).into()),
+    });
+}
+
+/// Spawns the UI camera and the two text nodes the scoreboard/game-over
+/// systems keep in sync: the running score in the corner, and a hidden
+/// overlay that `game_over` reveals when the snake dies.
+fn ui_setup(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.spawn_bundle(UiCameraBundle::default());
+
+    let font = asset_server.load("fonts/FiraSans-Bold.ttf");
+
+    commands
+        .spawn_bundle(TextBundle {
+            style: Style {
+                align_self: AlignSelf::FlexEnd,
+                position_type: PositionType::Absolute,
+                position: Rect {
+                    top: Val::Px(10.0),
+                    left: Val::Px(10.0),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            text: Text::with_section(
+                "Score: 0  Best: 0",
+                TextStyle {
+                    font: font.clone(),
+                    font_size: 30.0,
+                    color: Color::WHITE,
+                },
+                Default::default(),
+            ),
+            ..Default::default()
+        })
+        .insert(ScoreboardText);
+
+    commands
+        .spawn_bundle(TextBundle {
+            style: Style {
+                align_self: AlignSelf::Center,
+                position_type: PositionType::Absolute,
+                margin: Rect {
+                    left: Val::Auto,
+                    right: Val::Auto,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            text: Text::with_section(
+                "",
+                TextStyle {
+                    font,
+                    font_size: 50.0,
+                    color: Color::WHITE,
+                },
+                TextAlignment {
+                    horizontal: HorizontalAlign::Center,
+                    ..Default::default()
+                },
+            ),
+            visible: Visible {
+                is_visible: false,
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .insert(GameOverText);
+}
+
+fn snake_setup(
+    mut commands: Commands,
+    mut snake_entities: ResMut<SnakeEntities>,
+    snake_material: Res<SnakeMaterials>,
+    mut game_over_visible: Query<&mut Visible, With<GameOverText>>,
+) {
+    for mut visible in game_over_visible.iter_mut() {
+        visible.is_visible = false;
+    }
+
+    snake_entities.0 = vec![
+        commands
+            .spawn_bundle(SpriteBundle {
+                material: snake_material.head_material.clone(),
+                sprite: Sprite::new(Vec2::new(10.0, 10.0)),
+                ..Default::default()
+            })
+            .insert(Position { x: 3, y: 3 })
+            .insert(Size::square(0.8))
+            .insert(SnakeHead {
+                direction: SnakeMoveDirection::Up,
+                intention: SnakeMoveDirection::Up,
+            })
+            .id(),
+        spawn_segment(
+            &mut commands,
+            &snake_material.segment_material,
+            Position { x: 3, y: 2 },
+        ),
+    ];
+}
+
+fn size_scaling(config: Res<GameConfig>, windows: Res<Windows>, mut q: Query<(&Size, &mut Sprite)>) {
+    let window = windows.get_primary().unwrap();
+    for (sprite_size, mut sprite) in q.iter_mut() {
+        sprite.size = Vec2::new(
+            sprite_size.width / config.arena_width as f32 * window.width() as f32,
+            sprite_size.height / config.arena_height as f32 * window.height() as f32,
+        )
+    }
+}
+
+struct SnakeHead {
+    /// The direction the head actually moved on its last step. Only the
+    /// movement step is allowed to change this.
+    direction: SnakeMoveDirection,
+    /// The direction queued up by player input, validated against `direction`
+    /// so it can never be written as a straight reversal.
+    intention: SnakeMoveDirection,
+}
+
+struct SnakeMaterials {
+    head_material: Handle<ColorMaterial>,
+    segment_material: Handle<ColorMaterial>,
+    food_material: Handle<ColorMaterial>,
+}
+
+/// How many extra turns the player is allowed to queue up ahead of the
+/// currently committed movement tick.
+const PENDING_INTENTIONS_DEPTH: usize = 2;
+
+#[derive(Default)]
+struct PendingIntentions(VecDeque<SnakeMoveDirection>);
+
+/// Attempts to queue `dir` behind whatever is already pending. Rejects a
+/// turn that would reverse the last queued direction (or `current_direction`
+/// if nothing is queued yet), a repeat of that same direction, and anything
+/// once the queue is already `PENDING_INTENTIONS_DEPTH` deep.
+fn try_queue_intention(
+    pending: &mut VecDeque<SnakeMoveDirection>,
+    current_direction: SnakeMoveDirection,
+    dir: SnakeMoveDirection,
+) {
+    let last_queued = pending.back().copied().unwrap_or(current_direction);
+    if dir != last_queued
+        && dir != last_queued.opposite()
+        && pending.len() < PENDING_INTENTIONS_DEPTH
+    {
+        pending.push_back(dir);
+    }
+}
+
+/// Copies `intention` into the committed direction for this movement tick,
+/// unless that would be a direct reversal of `current_direction`, in which
+/// case the snake keeps moving the way it was already going.
+fn commit_intention(
+    current_direction: SnakeMoveDirection,
+    intention: SnakeMoveDirection,
+) -> SnakeMoveDirection {
+    if intention != current_direction.opposite() {
+        intention
+    } else {
+        current_direction
+    }
+}
+
+/// Reads the keyboard every frame (independent of the snake's fixed
+/// movement step) and buffers a validated turn for `snake_movement` to
+/// consume on its next tick.
+fn snake_input(
+    keyboard_input: Res<Input<KeyCode>>,
+    mut pending_intentions: ResMut<PendingIntentions>,
+    heads: Query<&SnakeHead>,
+) {
+    let pressed_dir = if keyboard_input.pressed(KeyCode::Left) {
+        Some(SnakeMoveDirection::Left)
+    } else if keyboard_input.pressed(KeyCode::Down) {
+        Some(SnakeMoveDirection::Down)
+    } else if keyboard_input.pressed(KeyCode::Up) {
+        Some(SnakeMoveDirection::Up)
+    } else if keyboard_input.pressed(KeyCode::Right) {
+        Some(SnakeMoveDirection::Right)
+    } else {
+        None
+    };
+
+    let dir = match pressed_dir {
+        Some(dir) => dir,
+        None => return,
+    };
+
+    if let Some(head) = heads.iter().next() {
+        try_queue_intention(&mut pending_intentions.0, head.direction, dir);
+    }
+}
+
+#[cfg(test)]
+mod pending_intentions_tests {
+    use super::*;
+
+    #[test]
+    fn rejects_a_direct_reversal_of_the_committed_direction() {
+        let mut pending = VecDeque::new();
+        try_queue_intention(&mut pending, SnakeMoveDirection::Right, SnakeMoveDirection::Left);
+        assert!(pending.is_empty());
+    }
+
+    #[test]
+    fn rejects_repeating_the_same_queued_direction() {
+        let mut pending = VecDeque::new();
+        try_queue_intention(&mut pending, SnakeMoveDirection::Right, SnakeMoveDirection::Up);
+        try_queue_intention(&mut pending, SnakeMoveDirection::Right, SnakeMoveDirection::Up);
+        assert_eq!(pending, VecDeque::from(vec![SnakeMoveDirection::Up]));
+    }
+
+    #[test]
+    fn rejects_queuing_past_the_pending_depth() {
+        let mut pending = VecDeque::new();
+        try_queue_intention(&mut pending, SnakeMoveDirection::Right, SnakeMoveDirection::Up);
+        try_queue_intention(&mut pending, SnakeMoveDirection::Right, SnakeMoveDirection::Left);
+        try_queue_intention(&mut pending, SnakeMoveDirection::Right, SnakeMoveDirection::Down);
+        assert_eq!(
+            pending,
+            VecDeque::from(vec![SnakeMoveDirection::Up, SnakeMoveDirection::Left])
+        );
+    }
+
+    #[test]
+    fn two_turns_queued_within_one_tick_apply_in_order_across_two_ticks() {
+        // The bug this fix targets: turning Up then Left before the snake's
+        // next movement tick used to let the second turn slip past validation.
+        let mut pending = VecDeque::new();
+        try_queue_intention(&mut pending, SnakeMoveDirection::Right, SnakeMoveDirection::Up);
+        try_queue_intention(&mut pending, SnakeMoveDirection::Right, SnakeMoveDirection::Left);
+
+        let mut committed = SnakeMoveDirection::Right;
+
+        let first_intention = pending.pop_front().unwrap();
+        committed = commit_intention(committed, first_intention);
+        assert_eq!(committed, SnakeMoveDirection::Up);
+
+        let second_intention = pending.pop_front().unwrap();
+        committed = commit_intention(committed, second_intention);
+        assert_eq!(committed, SnakeMoveDirection::Left);
+    }
+
+    #[test]
+    fn commit_intention_never_applies_a_reversal() {
+        let committed = commit_intention(SnakeMoveDirection::Up, SnakeMoveDirection::Down);
+        assert_eq!(committed, SnakeMoveDirection::Up);
+    }
+}
+
+fn snake_movement(
+    config: Res<GameConfig>,
+    awaiting_restart: Res<AwaitingRestart>,
+    snake_entities: Res<SnakeEntities>,
+    mut last_tail_position: ResMut<LastTailPosition>,
+    mut game_over_events: EventWriter<GameOverEvent>,
+    mut pending_intentions: ResMut<PendingIntentions>,
+    mut heads: Query<(Entity, &mut SnakeHead)>,
+    mut positions: Query<&mut Position, Or<(With<SnakeSegment>, With<SnakeHead>)>>,
+) {
+    if awaiting_restart.0 {
+        return;
+    }
+
+    if let Some((head_entity, mut head)) = heads.iter_mut().next() {
+        if let Some(intention) = pending_intentions.0.pop_front() {
+            head.intention = intention;
+        }
+
+        head.direction = commit_intention(head.direction, head.intention);
+
+        // update positions
+        let snake_positions = snake_entities
+            .0
+            .iter()
+            .map_while(|e| match positions.get_mut(*e) {
+                Ok(pos) => Some(*pos),
+                Err(_) => None,
+            })
+            .collect::<Vec<Position>>();
+
+        snake_positions
+            .iter()
+            .zip(snake_entities.0.iter().skip(1))
+            .for_each(|(pos, ent)| match positions.get_mut(*ent) {
+                Ok(mut x) => *x = *pos,
+                Err(_) => {}
+            });
+
+        last_tail_position.0 = Some(*snake_positions.last().unwrap());
+
+        // change head positions
+        let mut head_pos = positions.get_mut(head_entity).unwrap();
+        match &head.direction {
+            SnakeMoveDirection::Left => {
+                head_pos.x -= 1;
+            }
+            SnakeMoveDirection::Right => {
+                head_pos.x += 1;
+            }
+            SnakeMoveDirection::Up => {
+                head_pos.y += 1;
+            }
+            SnakeMoveDirection::Down => {
+                head_pos.y -= 1;
+            }
+        }
+
+        // wall behavior
+        match config.wall_mode {
+            WallMode::Solid => {
+                if head_pos.x < 0
+                    || head_pos.y < 0
+                    || head_pos.x as u32 >= config.arena_width
+                    || head_pos.y as u32 >= config.arena_height
+                {
+                    game_over_events.send(GameOverEvent);
+                }
+            }
+            WallMode::Wrap => {
+                head_pos.x = wrap_coordinate(head_pos.x, config.arena_width);
+                head_pos.y = wrap_coordinate(head_pos.y, config.arena_height);
+            }
+        }
+
+        if snake_positions.contains(&head_pos) {
+            game_over_events.send(GameOverEvent);
+        }
+    }
+}
+
+/// Wraps a single axis coordinate that just stepped one cell past an edge
+/// back onto the opposite side of a `bound`-wide arena.
+fn wrap_coordinate(value: i32, bound: u32) -> i32 {
+    (value + bound as i32) % bound as i32
+}
+
+#[cfg(test)]
+mod wrap_coordinate_tests {
+    use super::*;
+
+    #[test]
+    fn wraps_below_zero_to_the_opposite_edge() {
+        assert_eq!(wrap_coordinate(-1, 10), 9);
+    }
+
+    #[test]
+    fn wraps_at_the_upper_bound_back_to_zero() {
+        assert_eq!(wrap_coordinate(10, 10), 0);
+    }
+
+    #[test]
+    fn leaves_in_bounds_values_unchanged() {
+        assert_eq!(wrap_coordinate(5, 10), 5);
+    }
+}
+
+fn position_translation(
+    config: Res<GameConfig>,
+    windows: Res<Windows>,
+    mut q: Query<(&Position, &mut Transform)>,
+) {
+    fn convert(pos: f32, bound_window: f32, bound_game: f32) -> f32 {
+        let tile_size = bound_window / bound_game;
+        pos / bound_game * bound_window - (bound_window / 2.0) + (tile_size / 2.0)
+    }
+    let window = windows.get_primary().unwrap();
+    for (pos, mut transform) in q.iter_mut() {
+        transform.translation = Vec3::new(
+            convert(pos.x as f32, window.width() as f32, config.arena_width as f32),
+            convert(pos.y as f32, window.height() as f32, config.arena_height as f32),
+            0.0,
+        );
+    }
+}
+
+struct Food;
+
+const FOOD_SPAWN_ATTEMPTS: u32 = 3;
+
+fn food_spawner(
+    mut commands: Commands,
+    config: Res<GameConfig>,
+    awaiting_restart: Res<AwaitingRestart>,
+    snake_materials: Res<SnakeMaterials>,
+    occupied_positions: Query<&Position, Or<(With<SnakeSegment>, With<SnakeHead>)>>,
+) {
+    if awaiting_restart.0 {
+        return;
+    }
+
+    let occupied = occupied_positions.iter().copied().collect::<Vec<Position>>();
+
+    let position = (0..FOOD_SPAWN_ATTEMPTS)
+        .map(|_| Position {
+            x: (random::<f32>() * (config.arena_width as f32)) as i32,
+            y: (random::<f32>() * (config.arena_height as f32)) as i32,
+        })
+        .find(|pos| !occupied.contains(pos))
+        .or_else(|| free_cell(&config, &occupied));
+
+    let position = match position {
+        Some(position) => position,
+        None => return,
+    };
+
+    commands
+        .spawn_bundle(SpriteBundle {
+            material: snake_materials.food_material.clone(),
+            sprite: Sprite::new(Vec2::new(10.0, 10.0)),
+            ..Default::default()
+        })
+        .insert(Food)
+        .insert(position)
+        .insert(Size::square(0.8));
+}
+
+/// Every cell of the arena that isn't currently occupied by the snake.
+fn enumerate_free_cells(config: &GameConfig, occupied: &[Position]) -> Vec<Position> {
+    (0..config.arena_width as i32)
+        .flat_map(|x| (0..config.arena_height as i32).map(move |y| Position { x, y }))
+        .filter(|pos| !occupied.contains(pos))
+        .collect()
+}
+
+/// Enumerates the remaining free cells and picks one uniformly at random.
+/// Used once the handful of random attempts in `food_spawner` all land on
+/// an occupied tile, which becomes likely once the snake fills most of the board.
+fn free_cell(config: &GameConfig, occupied: &[Position]) -> Option<Position> {
+    let free_cells = enumerate_free_cells(config, occupied);
+
+    if free_cells.is_empty() {
+        return None;
+    }
+
+    let index = (random::<f32>() * free_cells.len() as f32) as usize;
+    free_cells.get(index).copied()
+}
+
+#[cfg(test)]
+mod free_cell_tests {
+    use super::*;
+
+    #[test]
+    fn enumerates_every_cell_when_nothing_is_occupied() {
+        let config = GameConfig {
+            arena_width: 2,
+            arena_height: 2,
+            ..Default::default()
+        };
+
+        let mut free_cells = enumerate_free_cells(&config, &[]);
+        free_cells.sort_by_key(|pos| (pos.x, pos.y));
+
+        assert_eq!(
+            free_cells,
+            vec![
+                Position { x: 0, y: 0 },
+                Position { x: 0, y: 1 },
+                Position { x: 1, y: 0 },
+                Position { x: 1, y: 1 },
+            ]
+        );
+    }
+
+    #[test]
+    fn excludes_occupied_cells() {
+        let config = GameConfig {
+            arena_width: 2,
+            arena_height: 2,
+            ..Default::default()
+        };
+        let occupied = [
+            Position { x: 0, y: 0 },
+            Position { x: 0, y: 1 },
+            Position { x: 1, y: 0 },
+        ];
+
+        assert_eq!(
+            enumerate_free_cells(&config, &occupied),
+            vec![Position { x: 1, y: 1 }]
+        );
+    }
+
+    #[test]
+    fn free_cell_returns_none_once_the_board_is_full() {
+        let config = GameConfig {
+            arena_width: 1,
+            arena_height: 1,
+            ..Default::default()
+        };
+        let occupied = [Position { x: 0, y: 0 }];
+
+        assert_eq!(free_cell(&config, &occupied), None);
+    }
+}
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+enum SnakeMoveDirection {
+    Left,
+    Up,
+    Right,
+    Down,
+}
+
+impl SnakeMoveDirection {
+    fn opposite(self) -> Self {
+        match self {
+            Self::Left => Self::Right,
+            Self::Right => Self::Left,
+            Self::Up => Self::Down,
+            Self::Down => Self::Up,
+        }
+    }
+}
+
+struct SnakeSegment;
+
+#[derive(Default)]
+struct SnakeEntities(Vec<Entity>);
+
+fn spawn_segment(
+    commands: &mut Commands,
+    material: &Handle<ColorMaterial>,
+    position: Position,
+) -> Entity {
+    commands
+        .spawn_bundle(SpriteBundle {
+            material: material.clone(),
+            ..Default::default()
+        })
+        .insert(SnakeSegment)
+        .insert(position)
+        .insert(Size::square(0.65))
+        .id()
+}
+
+struct GrowthEvent;
+
+fn snake_eating(
+    mut commands: Commands,
+    mut growth_events: EventWriter<GrowthEvent>,
+    food_positions: Query<(Entity, &Position), With<Food>>,
+    head_positions: Query<&Position, With<SnakeHead>>,
+) {
+    for head_pos in head_positions.iter() {
+        for (ent, food_pos) in food_positions.iter() {
+            if food_pos == head_pos {
+                commands.entity(ent).despawn();
+                growth_events.send(GrowthEvent {});
+            }
+        }
+    }
+}
+
+#[derive(Default)]
+struct LastTailPosition(Option<Position>);
+
+/// Tracks the current run's score and the best score seen so far. `best`
+/// is never reset so it survives a game over into the next run.
+#[derive(Default)]
+struct Score {
+    current: u32,
+    best: u32,
+}
+
+impl Score {
+    /// Folds `current` into `best` and resets `current` for the next run,
+    /// returning the final score the run ended on.
+    fn finish_run(&mut self) -> u32 {
+        self.best = self.best.max(self.current);
+        let final_score = self.current;
+        self.current = 0;
+        final_score
+    }
+}
+
+#[cfg(test)]
+mod score_tests {
+    use super::*;
+
+    #[test]
+    fn finish_run_raises_best_and_resets_current() {
+        let mut score = Score { current: 7, best: 3 };
+        let final_score = score.finish_run();
+        assert_eq!(final_score, 7);
+        assert_eq!(score.best, 7);
+        assert_eq!(score.current, 0);
+    }
+
+    #[test]
+    fn finish_run_keeps_the_existing_best_if_higher() {
+        let mut score = Score { current: 2, best: 9 };
+        let final_score = score.finish_run();
+        assert_eq!(final_score, 2);
+        assert_eq!(score.best, 9);
+        assert_eq!(score.current, 0);
+    }
+}
+
+struct ScoreboardText;
+
+struct GameOverText;
+
+/// Set while the "Game Over" overlay is up, so the board stays frozen and
+/// `restart_game` knows to respawn the snake on the player's next move.
+#[derive(Default)]
+struct AwaitingRestart(bool);
+
+fn snake_growth(
+    mut commands: Commands,
+    last_tail_position: Res<LastTailPosition>,
+    mut growth_events: EventReader<GrowthEvent>,
+    mut snake_entities: ResMut<SnakeEntities>,
+    mut score: ResMut<Score>,
+    materials: Res<SnakeMaterials>,
+) {
+    for _event in growth_events.iter() {
+        snake_entities.0.push(spawn_segment(
+            &mut commands,
+            &materials.segment_material,
+            last_tail_position.0.unwrap(),
+        ));
+        score.current += 1;
+    }
+}
+
+fn scoreboard(score: Res<Score>, mut query: Query<&mut Text, With<ScoreboardText>>) {
+    for mut text in query.iter_mut() {
+        text.sections[0].value = format!("Score: {}  Best: {}", score.current, score.best);
+    }
+}
+
+struct GameOverEvent;
+
+/// Reveals the "Game Over" overlay and freezes the board. The actual
+/// respawn is left to `restart_game`, so the overlay survives at least
+/// one rendered frame instead of being hidden again in the same tick.
+fn game_over(
+    mut game_over_event: EventReader<GameOverEvent>,
+    mut score: ResMut<Score>,
+    mut awaiting_restart: ResMut<AwaitingRestart>,
+    mut game_over_message: Query<&mut Text, With<GameOverText>>,
+    mut game_over_visible: Query<&mut Visible, With<GameOverText>>,
+) {
+    if game_over_event.iter().next().is_some() {
+        let final_score = score.finish_run();
+
+        for mut text in game_over_message.iter_mut() {
+            text.sections[0].value = format!("Game Over — Score: {}", final_score);
+        }
+        for mut visible in game_over_visible.iter_mut() {
+            visible.is_visible = true;
+        }
+
+        awaiting_restart.0 = true;
+    }
+}
+
+/// Waits for a direction key press while the overlay is up, then clears
+/// the old board and respawns the snake for the next run.
+fn restart_game(
+    mut commands: Commands,
+    keyboard_input: Res<Input<KeyCode>>,
+    mut awaiting_restart: ResMut<AwaitingRestart>,
+    snake_entities: ResMut<SnakeEntities>,
+    materials: Res<SnakeMaterials>,
+    food: Query<Entity, With<Food>>,
+    game_over_visible: Query<&mut Visible, With<GameOverText>>,
+) {
+    if !awaiting_restart.0 {
+        return;
+    }
+
+    let restart_pressed = [
+        KeyCode::Left,
+        KeyCode::Right,
+        KeyCode::Up,
+        KeyCode::Down,
+    ]
+    .iter()
+    .any(|key| keyboard_input.just_pressed(*key));
+
+    if !restart_pressed {
+        return;
+    }
+
+    awaiting_restart.0 = false;
+
+    for ent in food.iter().chain(snake_entities.0.clone()) {
+        commands.entity(ent).despawn();
+    }
+    // snake_setup hides the overlay again once the new run's board is spawned
+    snake_setup(commands, snake_entities, materials, game_over_visible);
+}